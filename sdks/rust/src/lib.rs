@@ -1,9 +1,13 @@
 //! Mathison Rust SDK
 //! Client for Mathison API
 
+use bytes::Bytes;
+use futures::stream::{BoxStream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// Errors that can occur when using the Mathison client
 #[derive(Debug)]
@@ -11,6 +15,7 @@ pub enum Error {
     HttpError(String),
     ParseError(String),
     ApiError(String),
+    RateLimited { retry_after: Duration },
 }
 
 impl std::fmt::Display for Error {
@@ -19,6 +24,9 @@ impl std::fmt::Display for Error {
             Error::HttpError(msg) => write!(f, "HTTP error: {}", msg),
             Error::ParseError(msg) => write!(f, "Parse error: {}", msg),
             Error::ApiError(msg) => write!(f, "API error: {}", msg),
+            Error::RateLimited { retry_after } => {
+                write!(f, "Rate limited, retry after {:?}", retry_after)
+            }
         }
     }
 }
@@ -37,6 +45,19 @@ pub struct ChatMessage {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Handle to an uploaded media object, returned by the media endpoint and
+/// attachable to chat messages and beams.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaHandle {
+    pub media_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
 /// Send message response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendMessageResponse {
@@ -44,6 +65,20 @@ pub struct SendMessageResponse {
     pub stream_id: Option<String>,
 }
 
+/// An incremental chunk of a streamed chat response.
+///
+/// Each SSE event carries a `delta` (the new content appended since the last
+/// chunk) and a `done` flag that is set on the final event. The completed
+/// [`ChatMessage`] is attached to the terminal chunk when the server sends it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatChunk {
+    pub delta: String,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<ChatMessage>,
+}
+
 /// Chat history response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatHistoryResponse {
@@ -53,6 +88,26 @@ pub struct ChatHistoryResponse {
     pub offset: usize,
 }
 
+/// Inclusive time range (epoch milliseconds) for history exports.
+#[derive(Debug, Clone, Default)]
+pub struct TimeRange {
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+}
+
+impl TimeRange {
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(start) = self.start_ms {
+            params.push(("start", start.to_string()));
+        }
+        if let Some(end) = self.end_ms {
+            params.push(("end", end.to_string()));
+        }
+        params
+    }
+}
+
 /// Beam
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Beam {
@@ -88,6 +143,170 @@ pub struct BeamQuery {
     pub limit: Option<usize>,
 }
 
+impl BeamQuery {
+    /// Start composing a query with the fluent [`BeamQueryBuilder`].
+    pub fn builder() -> BeamQueryBuilder {
+        BeamQueryBuilder::default()
+    }
+
+    /// Serialize every set field into query-string parameters. `tags` and
+    /// `kinds` are emitted as repeated parameters.
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(ref text) = self.text {
+            params.push(("text", text.clone()));
+        }
+        if let Some(ref tags) = self.tags {
+            for tag in tags {
+                params.push(("tags", tag.clone()));
+            }
+        }
+        if let Some(ref kinds) = self.kinds {
+            for kind in kinds {
+                params.push(("kinds", kind.clone()));
+            }
+        }
+        if let Some(include_dead) = self.include_dead {
+            params.push(("include_dead", include_dead.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        params
+    }
+}
+
+/// Fluent builder for [`BeamQuery`].
+#[derive(Debug, Clone, Default)]
+pub struct BeamQueryBuilder {
+    query: BeamQuery,
+}
+
+impl BeamQueryBuilder {
+    /// Filter by free-text search.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.query.text = Some(text.into());
+        self
+    }
+
+    /// Require a tag; may be called repeatedly to require several.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.query.tags.get_or_insert_with(Vec::new).push(tag.into());
+        self
+    }
+
+    /// Restrict to a beam kind; may be called repeatedly.
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.query.kinds.get_or_insert_with(Vec::new).push(kind.into());
+        self
+    }
+
+    /// Include tombstoned/retired beams in the results.
+    pub fn include_dead(mut self, include_dead: bool) -> Self {
+        self.query.include_dead = Some(include_dead);
+        self
+    }
+
+    /// Cap the number of results per page.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.query.limit = Some(limit);
+        self
+    }
+
+    /// Finish building the query.
+    pub fn build(self) -> BeamQuery {
+        self.query
+    }
+}
+
+/// Default page size used when a paginated query does not specify a limit.
+const DEFAULT_PAGE_LIMIT: usize = 50;
+
+/// Identifies the query a [`Page`] was produced from, so it can refetch the
+/// adjacent pages.
+#[derive(Debug, Clone)]
+enum PageQuery {
+    Beams(BeamQuery),
+    ChatHistory,
+}
+
+/// A single page of results together with the query that produced it.
+///
+/// Use [`Page::next_page`] / [`Page::prev_page`] to walk the result set without
+/// hand-rolling offset arithmetic. The next page starts at `offset +
+/// items.len()` and the previous one at `offset - limit` (clamped to zero);
+/// `next_page` returns `Ok(None)` once `offset + items.len()` reaches `total`.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    query: PageQuery,
+}
+
+impl<T> Page<T> {
+    /// Whether more items exist after this page.
+    pub fn has_next(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+
+    fn next_offset(&self) -> Option<usize> {
+        if self.has_next() {
+            // Advance by the number of items actually returned, not the
+            // requested `limit`: when the server caps a page below `limit`,
+            // jumping by `limit` would skip the records in between.
+            Some(self.offset + self.items.len())
+        } else {
+            None
+        }
+    }
+
+    fn prev_offset(&self) -> Option<usize> {
+        if self.offset == 0 {
+            None
+        } else {
+            Some(self.offset.saturating_sub(self.limit))
+        }
+    }
+}
+
+impl Page<Beam> {
+    /// Fetch the next page of beams, or `Ok(None)` if this is the last page.
+    pub async fn next_page(&self, client: &MathisonClient) -> Result<Option<Page<Beam>>> {
+        match (self.next_offset(), &self.query) {
+            (Some(offset), PageQuery::Beams(q)) => Ok(Some(client.query_beams_page(q, offset).await?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Fetch the previous page of beams, or `Ok(None)` if this is the first page.
+    pub async fn prev_page(&self, client: &MathisonClient) -> Result<Option<Page<Beam>>> {
+        match (self.prev_offset(), &self.query) {
+            (Some(offset), PageQuery::Beams(q)) => Ok(Some(client.query_beams_page(q, offset).await?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Page<ChatMessage> {
+    /// Fetch the next page of chat history, or `Ok(None)` if exhausted.
+    pub async fn next_page(&self, client: &MathisonClient) -> Result<Option<Page<ChatMessage>>> {
+        match self.next_offset() {
+            Some(offset) => Ok(Some(client.chat_history_page(self.limit, offset).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the previous page of chat history, or `Ok(None)` if at the start.
+    pub async fn prev_page(&self, client: &MathisonClient) -> Result<Option<Page<ChatMessage>>> {
+        match self.prev_offset() {
+            Some(offset) => Ok(Some(client.chat_history_page(self.limit, offset).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
 /// Create beam request
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateBeamRequest {
@@ -99,6 +318,8 @@ pub struct CreateBeamRequest {
     pub body: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pinned: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<MediaHandle>>,
 }
 
 /// Update beam request
@@ -120,11 +341,331 @@ pub struct TombstoneBeamRequest {
     pub approval_token: Option<String>,
 }
 
+/// Refresh the access token once it is within this window of expiring.
+const TOKEN_REFRESH_SKEW: Duration = Duration::from_secs(30);
+
+const OAUTH_REGISTER_PATH: &str = "/oauth/apps";
+const OAUTH_AUTHORIZE_PATH: &str = "/oauth/authorize";
+const OAUTH_TOKEN_PATH: &str = "/oauth/token";
+
+/// Builder that registers an OAuth2 application with the server.
+///
+/// Configure the app metadata, then [`register`](Registration::register) to
+/// obtain a [`RegisteredApp`] carrying the issued client credentials.
+pub struct Registration {
+    base_url: String,
+    client: reqwest::Client,
+    client_name: String,
+    redirect_uri: String,
+    scopes: Vec<String>,
+}
+
+impl Registration {
+    /// Start registering an application against `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client,
+            client_name: String::new(),
+            redirect_uri: String::new(),
+            scopes: Vec::new(),
+        })
+    }
+
+    /// Set the human-readable application name.
+    pub fn client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = name.into();
+        self
+    }
+
+    /// Set the redirect URI the authorization code is delivered to.
+    pub fn redirect_uri(mut self, uri: impl Into<String>) -> Self {
+        self.redirect_uri = uri.into();
+        self
+    }
+
+    /// Request a single scope; may be called repeatedly.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// POST the application metadata and return the registered app.
+    pub async fn register(self) -> Result<RegisteredApp> {
+        let body = serde_json::json!({
+            "client_name": self.client_name,
+            "redirect_uri": self.redirect_uri,
+            "scopes": self.scopes,
+        });
+        let url = format!("{}{}", self.base_url, OAUTH_REGISTER_PATH);
+        let response = self
+            .client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
+        }
+        let creds: RegisteredCredentials = response
+            .json()
+            .await
+            .map_err(|e| Error::ParseError(e.to_string()))?;
+        Ok(RegisteredApp {
+            base_url: self.base_url,
+            client: self.client,
+            client_id: creds.client_id,
+            client_secret: creds.client_secret,
+            redirect_uri: self.redirect_uri,
+            scopes: self.scopes,
+        })
+    }
+}
+
+/// Client credentials as returned by the registration endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct RegisteredCredentials {
+    client_id: String,
+    #[serde(default)]
+    client_secret: Option<String>,
+}
+
+/// A registered OAuth2 application, ready to drive the authorization flow.
+pub struct RegisteredApp {
+    base_url: String,
+    client: reqwest::Client,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    redirect_uri: String,
+    scopes: Vec<String>,
+}
+
+impl RegisteredApp {
+    /// Build the URL the user is redirected to in order to authorize the app.
+    pub fn authorize_url(&self, state: &str) -> Result<String> {
+        let scope = self.scopes.join(" ");
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}{}", self.base_url, OAUTH_AUTHORIZE_PATH),
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("scope", scope.as_str()),
+                ("state", state),
+            ],
+        )
+        .map_err(|e| Error::HttpError(e.to_string()))?;
+        Ok(url.to_string())
+    }
+
+    /// Exchange an authorization code for an access/refresh token pair.
+    pub async fn exchange_code(&self, code: &str) -> Result<OAuthToken> {
+        let mut form = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code.to_string()),
+            ("client_id", self.client_id.clone()),
+            ("redirect_uri", self.redirect_uri.clone()),
+        ];
+        if let Some(ref secret) = self.client_secret {
+            form.push(("client_secret", secret.clone()));
+        }
+        let url = format!("{}{}", self.base_url, OAUTH_TOKEN_PATH);
+        let response = self
+            .client
+            .post(&url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| Error::ParseError(e.to_string()))
+    }
+}
+
+/// Token endpoint response for the authorization-code and refresh grants.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+}
+
+/// Stored delegated credentials, refreshed in place as they approach expiry.
+struct OAuthSession {
+    token_endpoint: String,
+    client_id: String,
+    client_secret: Option<String>,
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<SystemTime>,
+}
+
+impl OAuthSession {
+    fn from_token(app: &RegisteredApp, token: OAuthToken) -> Self {
+        Self {
+            token_endpoint: format!("{}{}", app.base_url, OAUTH_TOKEN_PATH),
+            client_id: app.client_id.clone(),
+            client_secret: app.client_secret.clone(),
+            expires_at: token.expires_in.map(|s| SystemTime::now() + Duration::from_secs(s)),
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+        }
+    }
+
+    /// Whether the token is within [`TOKEN_REFRESH_SKEW`] of expiring.
+    fn needs_refresh(&self) -> bool {
+        match self.expires_at {
+            Some(at) => SystemTime::now() + TOKEN_REFRESH_SKEW >= at,
+            None => false,
+        }
+    }
+
+    /// Exchange the refresh token for a fresh access token.
+    async fn refresh(&mut self, client: &reqwest::Client) -> Result<()> {
+        let refresh_token = match self.refresh_token {
+            Some(ref token) => token.clone(),
+            None => return Ok(()),
+        };
+        let mut form = vec![
+            ("grant_type", "refresh_token".to_string()),
+            ("refresh_token", refresh_token),
+            ("client_id", self.client_id.clone()),
+        ];
+        if let Some(ref secret) = self.client_secret {
+            form.push(("client_secret", secret.clone()));
+        }
+        let response = client
+            .post(&self.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
+        }
+        let token: OAuthToken = response
+            .json()
+            .await
+            .map_err(|e| Error::ParseError(e.to_string()))?;
+        self.access_token = token.access_token;
+        if token.refresh_token.is_some() {
+            self.refresh_token = token.refresh_token;
+        }
+        self.expires_at = token.expires_in.map(|s| SystemTime::now() + Duration::from_secs(s));
+        Ok(())
+    }
+}
+
+/// Default base delay for the exponential-backoff retry layer.
+const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Maximum consecutive `Last-Event-ID` reconnects before an SSE subscription
+/// gives up. The counter resets whenever the connection makes progress, so this
+/// only bounds a server that keeps closing without ever sending data.
+const SSE_MAX_RECONNECTS: u32 = 5;
+
+/// Builder for a [`MathisonClient`] with retry and backoff configured.
+pub struct MathisonClientBuilder {
+    base_url: String,
+    api_key: Option<String>,
+    timeout: Duration,
+    max_retries: u32,
+    base_backoff: Duration,
+    retry_non_idempotent: bool,
+}
+
+impl MathisonClientBuilder {
+    /// Start building a client for `base_url`. Retries are off by default.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            api_key: None,
+            timeout: Duration::from_secs(30),
+            max_retries: 0,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Authenticate with a static bearer API key.
+    pub fn api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Overall per-request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Maximum number of retries on retriable (429 / 5xx) responses.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Base delay for exponential backoff (doubled each attempt, plus jitter).
+    pub fn base_backoff(mut self, base_backoff: Duration) -> Self {
+        self.base_backoff = base_backoff;
+        self
+    }
+
+    /// Opt non-idempotent requests (POST/PATCH) into the retry layer; off by
+    /// default to avoid duplicating side effects.
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// Build the configured client.
+    pub fn build(self) -> Result<MathisonClient> {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        Ok(MathisonClient {
+            base_url: self.base_url.trim_end_matches('/').to_string(),
+            api_key: self.api_key,
+            client,
+            oauth: None,
+            max_retries: self.max_retries,
+            base_backoff: self.base_backoff,
+            retry_non_idempotent: self.retry_non_idempotent,
+        })
+    }
+}
+
 /// Mathison API client
 pub struct MathisonClient {
     base_url: String,
     api_key: Option<String>,
     client: reqwest::Client,
+    oauth: Option<Arc<tokio::sync::Mutex<OAuthSession>>>,
+    max_retries: u32,
+    base_backoff: Duration,
+    retry_non_idempotent: bool,
 }
 
 impl MathisonClient {
@@ -144,16 +685,49 @@ impl MathisonClient {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             api_key,
             client,
+            oauth: None,
+            max_retries: 0,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            retry_non_idempotent: false,
+        })
+    }
+
+    /// Start building a client with retry and backoff configuration.
+    pub fn builder(base_url: impl Into<String>) -> MathisonClientBuilder {
+        MathisonClientBuilder::new(base_url)
+    }
+
+    /// Create a client authenticated with delegated OAuth2 credentials.
+    ///
+    /// The access token is refreshed transparently before each request once it
+    /// comes within [`TOKEN_REFRESH_SKEW`] of expiring.
+    pub fn with_oauth(
+        base_url: impl Into<String>,
+        app: &RegisteredApp,
+        token: OAuthToken,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            client,
+            oauth: Some(Arc::new(tokio::sync::Mutex::new(OAuthSession::from_token(app, token)))),
+            max_retries: 0,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            retry_non_idempotent: false,
         })
     }
 
-    fn build_headers(&self) -> reqwest::header::HeaderMap {
+    fn build_headers(&self, bearer: Option<&str>) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::CONTENT_TYPE,
             "application/json".parse().unwrap(),
         );
-        if let Some(ref key) = self.api_key {
+        if let Some(key) = bearer {
             headers.insert(
                 reqwest::header::AUTHORIZATION,
                 format!("Bearer {}", key).parse().unwrap(),
@@ -162,6 +736,20 @@ impl MathisonClient {
         headers
     }
 
+    /// Resolve the bearer token for the next request, refreshing an expiring
+    /// OAuth2 access token first when necessary.
+    async fn bearer_token(&self) -> Result<Option<String>> {
+        if let Some(ref oauth) = self.oauth {
+            let mut session = oauth.lock().await;
+            if session.needs_refresh() {
+                session.refresh(&self.client).await?;
+            }
+            Ok(Some(session.access_token.clone()))
+        } else {
+            Ok(self.api_key.clone())
+        }
+    }
+
     async fn request<T: serde::de::DeserializeOwned>(
         &self,
         method: reqwest::Method,
@@ -170,18 +758,74 @@ impl MathisonClient {
         query: Option<&[(&str, String)]>,
     ) -> Result<T> {
         let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.request(method, &url).headers(self.build_headers());
+        let may_retry = is_idempotent(&method) || self.retry_non_idempotent;
+        let mut attempt: u32 = 0;
 
-        if let Some(q) = query {
-            req = req.query(q);
+        loop {
+            let bearer = self.bearer_token().await?;
+            let mut req = self
+                .client
+                .request(method.clone(), &url)
+                .headers(self.build_headers(bearer.as_deref()));
+
+            if let Some(q) = query {
+                req = req.query(q);
+            }
+            if let Some(ref b) = body {
+                req = req.json(b);
+            }
+
+            let response = req.send().await.map_err(|e| Error::HttpError(e.to_string()))?;
+            let status = response.status();
+
+            if status.is_success() {
+                return response
+                    .json()
+                    .await
+                    .map_err(|e| Error::ParseError(e.to_string()));
+            }
+
+            let retriable = status.as_u16() == 429 || status.is_server_error();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after);
+
+            if retriable && may_retry && attempt < self.max_retries {
+                let wait = retry_after.unwrap_or_else(|| self.backoff_delay(attempt));
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+                continue;
+            }
+
+            if status.as_u16() == 429 {
+                return Err(Error::RateLimited {
+                    retry_after: retry_after.unwrap_or_else(|| self.backoff_delay(attempt)),
+                });
+            }
+
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
         }
+    }
 
-        if let Some(b) = body {
-            req = req.json(&b);
+    /// POST a `multipart/form-data` body, letting reqwest compute the boundary
+    /// and `Content-Length`. Unlike [`request`](Self::request) this does not set
+    /// a JSON content type.
+    async fn request_multipart<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        form: reqwest::multipart::Form,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let bearer = self.bearer_token().await?;
+        let mut req = self.client.post(&url).multipart(form);
+        if let Some(key) = bearer {
+            req = req.bearer_auth(key);
         }
 
         let response = req.send().await.map_err(|e| Error::HttpError(e.to_string()))?;
-
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
@@ -194,6 +838,45 @@ impl MathisonClient {
             .map_err(|e| Error::ParseError(e.to_string()))
     }
 
+    /// Issue a request and return the raw streaming [`reqwest::Response`]
+    /// without deserializing it, so large bodies need not be buffered whole.
+    async fn request_raw(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        accept: &str,
+        query: Option<&[(&str, String)]>,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}{}", self.base_url, path);
+        let bearer = self.bearer_token().await?;
+        let mut req = self
+            .client
+            .request(method, &url)
+            .header(reqwest::header::ACCEPT, accept);
+        if let Some(key) = bearer {
+            req = req.bearer_auth(key);
+        }
+        if let Some(q) = query {
+            req = req.query(q);
+        }
+
+        let response = req.send().await.map_err(|e| Error::HttpError(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
+        }
+        Ok(response)
+    }
+
+    /// Exponential backoff for `attempt`, with a little jitter to avoid a
+    /// thundering herd.
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.min(16);
+        let base = self.base_backoff.saturating_mul(factor);
+        base + jitter(base)
+    }
+
     // ========== Health & Status ==========
 
     /// Check API health
@@ -219,6 +902,61 @@ impl MathisonClient {
         self.request(reqwest::Method::POST, "/api/chat/send", Some(body), None).await
     }
 
+    /// Send a chat message and consume the incremental token stream it produces.
+    ///
+    /// POSTs to `/api/chat/send`, then subscribes to the `stream_id` returned in
+    /// the [`SendMessageResponse`]. Errors if the server does not advertise a
+    /// stream for the message.
+    pub async fn send_message_streaming(
+        &self,
+        content: impl Into<String>,
+    ) -> Result<impl Stream<Item = Result<ChatChunk>>> {
+        let response = self.send_message(content).await?;
+        let stream_id = response
+            .stream_id
+            .ok_or_else(|| Error::ApiError("response did not include a stream_id".to_string()))?;
+        self.subscribe_stream(stream_id).await
+    }
+
+    /// Send a chat message carrying previously uploaded attachments.
+    pub async fn send_message_with_attachments(
+        &self,
+        content: impl Into<String>,
+        attachments: Vec<MediaHandle>,
+    ) -> Result<SendMessageResponse> {
+        let body = serde_json::json!({
+            "content": content.into(),
+            "attachments": attachments,
+        });
+        self.request(reqwest::Method::POST, "/api/chat/send", Some(body), None).await
+    }
+
+    /// Subscribe to an existing token stream by its `stream_id`.
+    ///
+    /// Opens a long-lived `GET /api/chat/stream/{stream_id}` connection with
+    /// `Accept: text/event-stream` and yields [`ChatChunk`] deltas as they
+    /// arrive. If the connection drops mid-stream it is transparently
+    /// re-established with a `Last-Event-ID` header so the server can resume.
+    pub async fn subscribe_stream(
+        &self,
+        stream_id: impl Into<String>,
+    ) -> Result<impl Stream<Item = Result<ChatChunk>>> {
+        let state = SseState {
+            client: self.client.clone(),
+            url: format!("{}/api/chat/stream/{}", self.base_url, stream_id.into()),
+            api_key: self.api_key.clone(),
+            oauth: self.oauth.clone(),
+            base_backoff: self.base_backoff,
+            last_event_id: None,
+            body: None,
+            buf: Vec::new(),
+            data: String::new(),
+            reconnects: 0,
+            done: false,
+        };
+        Ok(futures::stream::try_unfold(state, sse_next))
+    }
+
     /// Get chat history
     pub async fn get_chat_history(&self, limit: Option<usize>, offset: Option<usize>) -> Result<ChatHistoryResponse> {
         let mut query = Vec::new();
@@ -232,24 +970,176 @@ impl MathisonClient {
         self.request(reqwest::Method::GET, "/api/chat/history", None, query_ref).await
     }
 
+    /// Fetch a single page of chat history as a walkable [`Page`].
+    pub async fn chat_history_page(&self, limit: usize, offset: usize) -> Result<Page<ChatMessage>> {
+        let response = self.get_chat_history(Some(limit), Some(offset)).await?;
+        Ok(Page {
+            items: response.messages,
+            total: response.total,
+            limit: response.limit,
+            offset: response.offset,
+            query: PageQuery::ChatHistory,
+        })
+    }
+
+    /// Stream the entire chat history, fetching subsequent pages transparently
+    /// until `offset + items.len() >= total`.
+    pub fn chat_history_stream(&self, limit: usize) -> impl Stream<Item = Result<ChatMessage>> + '_ {
+        let cursor = PageCursor::new(limit.max(1));
+        futures::stream::try_unfold(cursor, move |mut cursor| async move {
+            loop {
+                if let Some(item) = cursor.buf.pop_front() {
+                    return Ok(Some((item, cursor)));
+                }
+                if cursor.exhausted() {
+                    return Ok(None);
+                }
+                let response = self.get_chat_history(Some(cursor.limit), Some(cursor.offset)).await?;
+                cursor.absorb(response.messages, response.total);
+                if cursor.last_batch == 0 {
+                    return Ok(None);
+                }
+            }
+        })
+    }
+
+    /// Export chat history within `range` as CSV, buffered into [`Bytes`].
+    pub async fn export_chat_history_csv(&self, range: TimeRange) -> Result<Bytes> {
+        let params = range.query_params();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/chat/history/export", "text/csv", params_ref)
+            .await?;
+        response.bytes().await.map_err(|e| Error::HttpError(e.to_string()))
+    }
+
+    /// Export chat history within `range` as CSV, streaming the body into
+    /// `writer` so very large exports are not buffered whole.
+    pub async fn export_chat_history_csv_to<W: Write>(&self, range: TimeRange, writer: &mut W) -> Result<()> {
+        let params = range.query_params();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/chat/history/export", "text/csv", params_ref)
+            .await?;
+        stream_to_writer(response, writer).await
+    }
+
+    /// Open a streaming, header-aware CSV reader over a chat-history export.
+    pub async fn read_chat_history_csv(&self, range: TimeRange) -> Result<CsvReader> {
+        let params = range.query_params();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/chat/history/export", "text/csv", params_ref)
+            .await?;
+        CsvReader::open(response).await
+    }
+
+    // ========== Media ==========
+
+    /// Upload a file and receive a [`MediaHandle`] for attaching it to messages
+    /// or beams.
+    pub async fn upload_attachment(
+        &self,
+        bytes: impl Into<Vec<u8>>,
+        filename: impl Into<String>,
+        mime: impl Into<String>,
+    ) -> Result<MediaHandle> {
+        let part = reqwest::multipart::Part::bytes(bytes.into())
+            .file_name(filename.into())
+            .mime_str(&mime.into())
+            .map_err(|e| Error::HttpError(e.to_string()))?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+        self.request_multipart("/api/media", form).await
+    }
+
     // ========== Beams ==========
 
-    /// Query beams
+    /// Query beams, serializing every provided filter into the query string.
     pub async fn query_beams(&self, query: Option<BeamQuery>) -> Result<BeamQueryResponse> {
-        let query_params = if let Some(q) = query {
-            let mut params = Vec::new();
-            if let Some(text) = q.text {
-                params.push(("text", text));
-            }
-            if let Some(limit) = q.limit {
-                params.push(("limit", limit.to_string()));
+        let params = query.as_ref().map(BeamQuery::query_params).unwrap_or_default();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        self.request(reqwest::Method::GET, "/api/beams", None, params_ref).await
+    }
+
+    /// Query beams at a given offset, returning a walkable [`Page`].
+    pub async fn query_beams_page(&self, query: &BeamQuery, offset: usize) -> Result<Page<Beam>> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let response = self.query_beams_offset(query, offset).await?;
+        Ok(Page {
+            items: response.beams,
+            total: response.total,
+            limit,
+            offset,
+            query: PageQuery::Beams(query.clone()),
+        })
+    }
+
+    /// Stream every matching beam, fetching subsequent pages transparently
+    /// until the result set is exhausted.
+    pub fn query_beams_all(&self, query: BeamQuery) -> impl Stream<Item = Result<Beam>> + '_ {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let cursor = PageCursor::new(limit.max(1));
+        futures::stream::try_unfold((query, cursor), move |(query, mut cursor)| async move {
+            loop {
+                if let Some(item) = cursor.buf.pop_front() {
+                    return Ok(Some((item, (query, cursor))));
+                }
+                if cursor.exhausted() {
+                    return Ok(None);
+                }
+                let response = self.query_beams_offset(&query, cursor.offset).await?;
+                cursor.absorb(response.beams, response.total);
+                if cursor.last_batch == 0 {
+                    return Ok(None);
+                }
             }
-            if query_params.is_empty() { None } else { Some(params.as_slice()) }
-        } else {
-            None
-        };
+        })
+    }
 
-        self.request(reqwest::Method::GET, "/api/beams", None, query_params).await
+    /// Serialize a [`BeamQuery`] with an explicit offset and issue the request.
+    async fn query_beams_offset(&self, query: &BeamQuery, offset: usize) -> Result<BeamQueryResponse> {
+        let mut params = query.query_params();
+        if query.limit.is_none() {
+            // Pin the server's page size to the walker's stride so paging never
+            // skips or duplicates records when the caller sets no explicit limit.
+            params.push(("limit", DEFAULT_PAGE_LIMIT.to_string()));
+        }
+        if offset > 0 {
+            params.push(("offset", offset.to_string()));
+        }
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        self.request(reqwest::Method::GET, "/api/beams", None, params_ref).await
+    }
+
+    /// Export matching beams as CSV, buffered into [`Bytes`].
+    pub async fn export_beams_csv(&self, query: Option<BeamQuery>) -> Result<Bytes> {
+        let params = query.as_ref().map(BeamQuery::query_params).unwrap_or_default();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/beams/export", "text/csv", params_ref)
+            .await?;
+        response.bytes().await.map_err(|e| Error::HttpError(e.to_string()))
+    }
+
+    /// Export matching beams as CSV, streaming the body into `writer` so very
+    /// large exports are not buffered whole.
+    pub async fn export_beams_csv_to<W: Write>(&self, query: Option<BeamQuery>, writer: &mut W) -> Result<()> {
+        let params = query.as_ref().map(BeamQuery::query_params).unwrap_or_default();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/beams/export", "text/csv", params_ref)
+            .await?;
+        stream_to_writer(response, writer).await
+    }
+
+    /// Open a streaming, header-aware CSV reader over a beam export.
+    pub async fn read_beams_csv(&self, query: Option<BeamQuery>) -> Result<CsvReader> {
+        let params = query.as_ref().map(BeamQuery::query_params).unwrap_or_default();
+        let params_ref = if params.is_empty() { None } else { Some(params.as_slice()) };
+        let response = self
+            .request_raw(reqwest::Method::GET, "/api/beams/export", "text/csv", params_ref)
+            .await?;
+        CsvReader::open(response).await
     }
 
     /// Get a specific beam
@@ -297,6 +1187,419 @@ impl MathisonClient {
     }
 }
 
+/// Copy a streaming response body into `writer`, one chunk at a time.
+async fn stream_to_writer<W: Write>(response: reqwest::Response, writer: &mut W) -> Result<()> {
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| Error::HttpError(e.to_string()))?;
+        writer.write_all(&chunk).map_err(|e| Error::HttpError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// A streaming, header-aware CSV reader over an export response body.
+///
+/// The first record is consumed as the header row; subsequent records are
+/// yielded on demand so the full export never needs to be held in memory.
+pub struct CsvReader {
+    headers: Vec<String>,
+    stream: BoxStream<'static, reqwest::Result<Bytes>>,
+    buf: Vec<u8>,
+    eof: bool,
+}
+
+impl CsvReader {
+    async fn open(response: reqwest::Response) -> Result<Self> {
+        let mut reader = Self {
+            headers: Vec::new(),
+            stream: response.bytes_stream().boxed(),
+            buf: Vec::new(),
+            eof: false,
+        };
+        reader.headers = reader.read_record().await?.unwrap_or_default();
+        Ok(reader)
+    }
+
+    /// The parsed header row.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Read the next raw record, or `Ok(None)` at end of stream.
+    async fn read_record(&mut self) -> Result<Option<Vec<String>>> {
+        loop {
+            if let Some((fields, consumed)) = parse_csv_record(&self.buf) {
+                self.buf.drain(..consumed);
+                return Ok(Some(fields));
+            }
+            if self.eof {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                if *self.buf.last().unwrap() != b'\n' {
+                    self.buf.push(b'\n');
+                }
+                return match parse_csv_record(&self.buf) {
+                    Some((fields, consumed)) => {
+                        self.buf.drain(..consumed);
+                        Ok(Some(fields))
+                    }
+                    None => {
+                        self.buf.clear();
+                        Ok(None)
+                    }
+                };
+            }
+            match self.stream.next().await {
+                Some(Ok(bytes)) => self.buf.extend_from_slice(&bytes),
+                Some(Err(e)) => return Err(Error::HttpError(e.to_string())),
+                None => self.eof = true,
+            }
+        }
+    }
+
+    /// Read the next record as a map of header name to value.
+    pub async fn next_record(&mut self) -> Result<Option<HashMap<String, String>>> {
+        match self.read_record().await? {
+            Some(fields) => Ok(Some(self.headers.iter().cloned().zip(fields).collect())),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Parse one complete CSV record (RFC 4180 quoting) from the front of `buf`.
+///
+/// Returns the parsed fields and the number of bytes consumed, or `None` when
+/// `buf` does not yet hold a terminated record.
+fn parse_csv_record(buf: &[u8]) -> Option<(Vec<String>, usize)> {
+    let mut fields = Vec::new();
+    let mut field: Vec<u8> = Vec::new();
+    let mut in_quotes = false;
+    let mut i = 0;
+
+    while i < buf.len() {
+        let c = buf[i];
+        if in_quotes {
+            if c == b'"' {
+                match buf.get(i + 1) {
+                    Some(b'"') => {
+                        field.push(b'"');
+                        i += 2;
+                    }
+                    Some(_) => {
+                        in_quotes = false;
+                        i += 1;
+                    }
+                    None => return None, // can't tell escaped quote from close yet
+                }
+            } else {
+                field.push(c);
+                i += 1;
+            }
+        } else {
+            match c {
+                b'"' if field.is_empty() => {
+                    in_quotes = true;
+                    i += 1;
+                }
+                b'"' => i += 1,
+                b',' => {
+                    fields.push(String::from_utf8_lossy(&field).into_owned());
+                    field.clear();
+                    i += 1;
+                }
+                b'\n' => {
+                    fields.push(String::from_utf8_lossy(&field).into_owned());
+                    return Some((fields, i + 1));
+                }
+                b'\r' => i += 1,
+                _ => {
+                    field.push(c);
+                    i += 1;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether a method is safe to retry without risking duplicate side effects.
+fn is_idempotent(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET
+            | reqwest::Method::HEAD
+            | reqwest::Method::PUT
+            | reqwest::Method::DELETE
+            | reqwest::Method::OPTIONS
+    )
+}
+
+/// Derive up to ~25% jitter from the clock's sub-second nanoseconds.
+fn jitter(base: Duration) -> Duration {
+    let span = base.as_millis() as u64 / 4;
+    if span == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(nanos % (span + 1))
+}
+
+/// Exponential backoff (with jitter) for SSE reconnects, mirroring
+/// [`MathisonClient::backoff_delay`] but usable from the free `sse_next` walker.
+fn sse_reconnect_delay(base: Duration, attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.min(16);
+    let base = base.saturating_mul(factor);
+    base + jitter(base)
+}
+
+/// Parse a `Retry-After` header value, accepting both the integer-seconds and
+/// the HTTP-date forms.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let target = parse_http_date(value)?;
+    let now = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(Duration::from_secs(target.saturating_sub(now)))
+}
+
+/// Parse an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) into epoch seconds.
+fn parse_http_date(value: &str) -> Option<u64> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    let day: i64 = parts[1].parse().ok()?;
+    let month: i64 = match parts[2] {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year: i64 = parts[3].parse().ok()?;
+    let time: Vec<&str> = parts[4].split(':').collect();
+    if time.len() != 3 {
+        return None;
+    }
+    let hour: i64 = time[0].parse().ok()?;
+    let minute: i64 = time[1].parse().ok()?;
+    let second: i64 = time[2].parse().ok()?;
+
+    // days-from-civil (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let epoch = days * 86400 + hour * 3600 + minute * 60 + second;
+    if epoch < 0 {
+        None
+    } else {
+        Some(epoch as u64)
+    }
+}
+
+/// Buffered cursor state for the page-walking streams, threaded through
+/// [`futures::stream::try_unfold`].
+struct PageCursor<T> {
+    buf: VecDeque<T>,
+    offset: usize,
+    limit: usize,
+    fetched: usize,
+    total: Option<usize>,
+    last_batch: usize,
+}
+
+impl<T> PageCursor<T> {
+    fn new(limit: usize) -> Self {
+        Self {
+            buf: VecDeque::new(),
+            offset: 0,
+            limit,
+            fetched: 0,
+            total: None,
+            last_batch: 0,
+        }
+    }
+
+    fn exhausted(&self) -> bool {
+        matches!(self.total, Some(total) if self.fetched >= total)
+    }
+
+    fn absorb(&mut self, items: Vec<T>, total: usize) {
+        self.last_batch = items.len();
+        self.fetched += items.len();
+        self.offset += items.len();
+        self.total = Some(total);
+        self.buf.extend(items);
+    }
+}
+
+/// In-flight state for an SSE subscription, threaded through
+/// [`futures::stream::try_unfold`].
+struct SseState {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    oauth: Option<Arc<tokio::sync::Mutex<OAuthSession>>>,
+    base_backoff: Duration,
+    last_event_id: Option<String>,
+    body: Option<BoxStream<'static, reqwest::Result<Bytes>>>,
+    buf: Vec<u8>,
+    data: String,
+    reconnects: u32,
+    done: bool,
+}
+
+/// Open (or re-open) the event-stream connection, carrying `Last-Event-ID` when
+/// resuming after a drop.
+async fn sse_open(state: &SseState) -> Result<BoxStream<'static, reqwest::Result<Bytes>>> {
+    let mut req = state
+        .client
+        .get(&state.url)
+        .header(reqwest::header::ACCEPT, "text/event-stream");
+    let bearer = if let Some(ref oauth) = state.oauth {
+        let mut session = oauth.lock().await;
+        if session.needs_refresh() {
+            session.refresh(&state.client).await?;
+        }
+        Some(session.access_token.clone())
+    } else {
+        state.api_key.clone()
+    };
+    if let Some(key) = bearer {
+        req = req.header(reqwest::header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    if let Some(ref id) = state.last_event_id {
+        req = req.header("Last-Event-ID", id.as_str());
+    }
+
+    let response = req.send().await.map_err(|e| Error::HttpError(e.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(Error::ApiError(format!("HTTP {}: {}", status, error_text)));
+    }
+    Ok(response.bytes_stream().boxed())
+}
+
+/// Pull the next [`ChatChunk`] out of the stream, parsing SSE framing and
+/// reconnecting on a mid-stream drop.
+async fn sse_next(mut state: SseState) -> Result<Option<(ChatChunk, SseState)>> {
+    if state.done {
+        return Ok(None);
+    }
+
+    loop {
+        // Drain every complete event already buffered before touching the
+        // network: the server may batch several events (including the final
+        // `done`) into one read, and may close right afterwards.
+        if let Some(chunk) = sse_drain_event(&mut state)? {
+            if chunk.done {
+                state.done = true;
+            }
+            return Ok(Some((chunk, state)));
+        }
+
+        if state.body.is_none() {
+            state.body = Some(sse_open(&state).await?);
+        }
+
+        match state.body.as_mut().unwrap().next().await {
+            Some(Ok(bytes)) => {
+                // Progress resets the reconnect budget.
+                state.reconnects = 0;
+                state.buf.extend_from_slice(&bytes);
+            }
+            other => {
+                // The connection ended before a `done` chunk arrived. Whether
+                // this was a transport failure (`Some(Err(_))` — reqwest yields
+                // this on reset/timeout) or a clean close (`None`), the stream
+                // is truncated: we never saw the final `done` flag, so we must
+                // not report a normal end of stream. Reconnect and resume —
+                // with a `Last-Event-ID` header when we have one, from the
+                // start otherwise — until the reconnect budget is spent, then
+                // surface the failure rather than a silent truncation.
+                let transport_err = match other {
+                    Some(Err(e)) => Some(e.to_string()),
+                    _ => None,
+                };
+                state.body = None;
+                state.buf.clear();
+                state.data.clear();
+                if state.reconnects >= SSE_MAX_RECONNECTS {
+                    return Err(Error::HttpError(match transport_err {
+                        Some(e) => format!(
+                            "SSE stream failed after {} reconnect attempts: {}",
+                            state.reconnects, e
+                        ),
+                        None => format!(
+                            "SSE stream closed before completion after {} reconnect attempts",
+                            state.reconnects
+                        ),
+                    }));
+                }
+                // Back off before resuming so a server that closes right after
+                // the handshake is not hammered in a tight loop.
+                tokio::time::sleep(sse_reconnect_delay(state.base_backoff, state.reconnects)).await;
+                state.reconnects += 1;
+            }
+        }
+    }
+}
+
+/// Pull one complete SSE event out of `state.buf`, returning the decoded
+/// [`ChatChunk`] when a blank line terminates a non-empty `data` payload.
+///
+/// Consumes every complete line it sees, accumulating `data:`/`id:` fields into
+/// `state`; returns `Ok(None)` when the buffer holds no further terminated
+/// event.
+fn sse_drain_event(state: &mut SseState) -> Result<Option<ChatChunk>> {
+    while let Some(pos) = state.buf.iter().position(|&b| b == b'\n') {
+        let raw: Vec<u8> = state.buf.drain(..=pos).collect();
+        let line = String::from_utf8_lossy(&raw);
+        let line = line.trim_end_matches('\n').trim_end_matches('\r');
+
+        if line.is_empty() {
+            // Blank line terminates an event.
+            if !state.data.is_empty() {
+                let chunk: ChatChunk = serde_json::from_str(&state.data)
+                    .map_err(|e| Error::ParseError(e.to_string()))?;
+                state.data.clear();
+                return Ok(Some(chunk));
+            }
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            let rest = rest.strip_prefix(' ').unwrap_or(rest);
+            if !state.data.is_empty() {
+                state.data.push('\n');
+            }
+            state.data.push_str(rest);
+        } else if let Some(rest) = line.strip_prefix("id:") {
+            state.last_event_id = Some(rest.trim().to_string());
+        }
+        // `event:`, comments (`:`...) and unknown fields are ignored.
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +1613,58 @@ mod tests {
     fn test_client_with_api_key() {
         let _client = MathisonClient::with_api_key("http://localhost:3000", Some("test-key".to_string())).unwrap();
     }
+
+    #[test]
+    fn test_parse_csv_record() {
+        let (fields, consumed) = parse_csv_record(b"a,\"b,c\",\"he said \"\"hi\"\"\"\nrest").unwrap();
+        assert_eq!(fields, vec!["a", "b,c", "he said \"hi\""]);
+        assert_eq!(consumed, b"a,\"b,c\",\"he said \"\"hi\"\"\"\n".len());
+    }
+
+    #[test]
+    fn test_parse_csv_record_incomplete() {
+        assert!(parse_csv_record(b"a,b,c").is_none());
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_beam_query_builder_params() {
+        let query = BeamQuery::builder()
+            .text("hello world")
+            .tag("a")
+            .tag("b")
+            .kind("note")
+            .include_dead(true)
+            .limit(25)
+            .build();
+        assert_eq!(
+            query.query_params(),
+            vec![
+                ("text", "hello world".to_string()),
+                ("tags", "a".to_string()),
+                ("tags", "b".to_string()),
+                ("kinds", "note".to_string()),
+                ("include_dead", "true".to_string()),
+                ("limit", "25".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_beam_query_query_string() {
+        let query = BeamQuery::builder().tag("a").tag("b").limit(10).build();
+        let url =
+            reqwest::Url::parse_with_params("http://host/api/beams", &query.query_params()).unwrap();
+        assert_eq!(url.query(), Some("tags=a&tags=b&limit=10"));
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        // Well-known epoch from RFC 7231's own example.
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(784111777));
+    }
 }